@@ -1,3 +1,4 @@
+#[cfg(feature = "ffi")]
 extern crate cbindgen;
 
 use std::io::Write;
@@ -6,8 +7,120 @@ use std::{env, fs};
 
 const VERSION: &'static str = env!("CARGO_PKG_VERSION");
 
+/// A type on the other side of the FFI boundary that needs a monomorphized
+/// `extern "C"` wrapper around `Serialize::to_writer`, because cbindgen
+/// cannot express a function generic over `io::Write`.
+struct ShimType {
+    /// Fully qualified path to the wrapped `pgp` type.
+    rust_type: &'static str,
+    /// The opaque handle type already declared for this packet, e.g.
+    /// `PgpUserAttribute(UserAttribute)`.
+    handle_type: &'static str,
+    /// Used to build the function name: `pgp_{name}{suffix}`.
+    name: &'static str,
+}
+
+const SHIM_TYPES: &[ShimType] = &[ShimType {
+    rust_type: "pgp::packet::UserAttribute",
+    handle_type: "PgpUserAttribute",
+    name: "user_attribute",
+}];
+
+/// Knobs for shim generation, analogous to the options `bindgen::Builder`
+/// exposes for its own generated wrappers.
+struct ShimConfig {
+    /// Suffix appended to `pgp_{name}` to build each wrapper's name.
+    suffix: String,
+}
+
+impl Default for ShimConfig {
+    fn default() -> Self {
+        ShimConfig {
+            suffix: "_serialize".to_string(),
+        }
+    }
+}
+
+/// Renders one `#[no_mangle] extern "C"` wrapper per entry in `SHIM_TYPES`,
+/// to be `include!`d from `src/lib.rs`.
+fn generate_serialize_shims(cfg: &ShimConfig) -> String {
+    let mut out = String::new();
+
+    for ty in SHIM_TYPES {
+        out.push_str(&format!(
+            r#"
+/// Serializes `handle` into `out`, which must be released with
+/// `pgp_buffer_free`. Returns `0` on success, non-zero on error, including
+/// when serialization panics — a panic must never unwind across this
+/// `extern "C"` boundary.
+///
+/// # Safety
+/// `handle` must be a live pointer returned by the matching `_parse`
+/// function, and `out` must point to a writable `PgpBuffer`.
+#[no_mangle]
+pub unsafe extern "C" fn pgp_{name}{suffix}(
+    handle: *const {handle_type},
+    out: *mut PgpBuffer,
+) -> i32 {{
+    let handle_ref = &(*handle).0;
+    let result = ::std::panic::catch_unwind(::std::panic::AssertUnwindSafe(|| {{
+        let mut buf: Vec<u8> = Vec::new();
+        match ::pgp::ser::Serialize::to_writer(handle_ref, &mut buf) {{
+            Ok(()) => Some(buf),
+            Err(_) => None,
+        }}
+    }}));
+
+    match result {{
+        Ok(Some(buf)) => {{
+            *out = PgpBuffer::from_vec(buf);
+            0
+        }}
+        Ok(None) | Err(_) => -1,
+    }}
+}}
+"#,
+            name = ty.name,
+            suffix = cfg.suffix,
+            handle_type = ty.handle_type,
+        ));
+
+        // `rust_type` isn't needed by the template itself (the handle already
+        // wraps it), but keeping it on `ShimType` documents what each shim is
+        // for and leaves room for a future non-handle-based shim flavor.
+        let _ = ty.rust_type;
+    }
+
+    out
+}
+
 fn main() {
+    // Generated under the same `ffi` feature as the cbindgen header below:
+    // `libpgp.h` declares `pgp_user_attribute_parse`/`_free` plus this
+    // generated `_serialize`, so building with `--features ffi` alone is
+    // enough to get the full, real round-trip through the crate.
+    if env::var("CARGO_FEATURE_FFI").is_ok() {
+        let cfg = ShimConfig::default();
+        let shims = generate_serialize_shims(&cfg);
+
+        let out_dir = env::var("OUT_DIR").unwrap();
+        fs::File::create(PathBuf::from(&out_dir).join("ffi_shims.rs"))
+            .unwrap()
+            .write_all(shims.as_bytes())
+            .unwrap();
+    }
+
+    #[cfg(feature = "ffi")]
+    generate_headers();
+}
+
+/// Emits `libpgp.h` and a pkg-config stub for it. Opt-in via the `ffi`
+/// feature, so pure-Rust consumers of this crate don't need to carry the
+/// cbindgen/pkg-config toolchain just to build.
+#[cfg(feature = "ffi")]
+fn generate_headers() {
     let crate_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let pkg_name = env::var("CARGO_PKG_NAME").unwrap();
 
     let target_path = PathBuf::from("target");
 
@@ -16,7 +129,7 @@ fn main() {
 
     let pkg_config = format!(
         include_str!("pgp.pc.in"),
-        name = env::var("CARGO_PKG_NAME").unwrap(),
+        name = pkg_name,
         description = env::var("CARGO_PKG_DESCRIPTION").unwrap(),
         url = env::var("CARGO_PKG_HOMEPAGE").unwrap_or("".to_string()),
         version = env::var("CARGO_PKG_VERSION").unwrap(),
@@ -25,14 +138,30 @@ fn main() {
     );
 
     fs::create_dir_all(target_path.join("pkgconfig")).unwrap();
-    fs::File::create(target_path.join("pkgconfig").join("distinst.pc.stub"))
-        .unwrap()
-        .write_all(&pkg_config.as_bytes())
-        .unwrap();
+    fs::File::create(
+        target_path
+            .join("pkgconfig")
+            .join(format!("{}.pc.stub", pkg_name)),
+    )
+    .unwrap()
+    .write_all(&pkg_config.as_bytes())
+    .unwrap();
 
     let cfg = cbindgen::Config::from_file(&format!("{}/cbindgen.toml", &crate_dir))
         .expect("invalid config");
 
+    generate_c_header(&crate_dir, cfg.clone());
+
+    // C++ header generation is opt-in: most consumers of `libpgp.h` only
+    // need the C ABI, and cbindgen's C++ mode additionally requires a
+    // namespace to be configured.
+    if env::var("PGP_FFI_CXX").is_ok() {
+        generate_cxx_header(&crate_dir, cfg);
+    }
+}
+
+/// Emits the plain C header, `libpgp.h`.
+fn generate_c_header(crate_dir: &str, cfg: cbindgen::Config) {
     let c = cbindgen::Builder::new()
         .with_config(cfg)
         .with_crate(crate_dir)
@@ -51,4 +180,30 @@ fn main() {
             std::process::exit(1);
         }
     }
+}
+
+/// Emits `libpgp.hpp`, following rust-bindgen's support for targeting C++
+/// as well as C: the packet handle types are wrapped in a `pgp` namespace,
+/// with Rust enums like `PgpUserAttributeTag` mapped to a C++ `enum class`
+/// instead of a raw `uint8_t`.
+fn generate_cxx_header(crate_dir: &str, mut cfg: cbindgen::Config) {
+    cfg.namespace = Some("pgp".to_string());
+    cfg.language = cbindgen::Language::Cxx;
+
+    let cxx = cbindgen::Builder::new()
+        .with_config(cfg)
+        .with_crate(crate_dir)
+        .with_header(format!("/* libpgp++ Header Version {} */", VERSION))
+        .with_language(cbindgen::Language::Cxx)
+        .generate();
+
+    match cxx {
+        Ok(res) => {
+            res.write_to_file("libpgp.hpp");
+        }
+        Err(err) => {
+            eprintln!("unable to generate c++ bindings: {:#?}", err);
+            std::process::exit(1);
+        }
+    }
 }
\ No newline at end of file
@@ -0,0 +1,35 @@
+/// An owned, C-compatible byte buffer.
+///
+/// Hands a `Vec<u8>`'s raw parts across the FFI boundary; must be released
+/// with `pgp_buffer_free` exactly once.
+#[repr(C)]
+pub struct PgpBuffer {
+    data: *mut u8,
+    len: usize,
+    cap: usize,
+}
+
+impl PgpBuffer {
+    pub(crate) fn from_vec(mut v: Vec<u8>) -> Self {
+        let data = v.as_mut_ptr();
+        let len = v.len();
+        let cap = v.capacity();
+        ::std::mem::forget(v);
+
+        PgpBuffer { data, len, cap }
+    }
+}
+
+/// Releases a `PgpBuffer` previously filled in by one of this crate's
+/// `_serialize` functions.
+///
+/// # Safety
+/// `buf` must have been produced by this library and must not be freed more
+/// than once.
+#[no_mangle]
+pub unsafe extern "C" fn pgp_buffer_free(buf: PgpBuffer) {
+    if buf.data.is_null() {
+        return;
+    }
+    drop(Vec::from_raw_parts(buf.data, buf.len, buf.cap));
+}
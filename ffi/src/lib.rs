@@ -0,0 +1,24 @@
+//! C-compatible FFI bindings for `pgp`.
+//!
+//! Every type crossing the boundary is either a plain `#[repr(C)]` value or
+//! an opaque boxed handle, following the approach `lightning-c-bindings`
+//! uses in its `c_types` module: ownership is always explicit, and every
+//! `_parse`/`_new` function is paired with a matching `_free`.
+
+extern crate pgp;
+
+mod buffer;
+mod user_attribute;
+
+pub use buffer::{pgp_buffer_free, PgpBuffer};
+pub use user_attribute::{
+    pgp_user_attribute_free, pgp_user_attribute_parse, pgp_user_attribute_tag, PgpUserAttribute,
+    PgpUserAttributeTag,
+};
+
+// `Serialize::to_writer` is generic over its writer, which cbindgen cannot
+// express, so `build.rs` emits one non-generic `extern "C"` wrapper per
+// `Serialize` implementor (see `SHIM_TYPES` there) instead of hand-writing
+// one for every packet type.
+#[cfg(feature = "ffi")]
+include!(concat!(env!("OUT_DIR"), "/ffi_shims.rs"));
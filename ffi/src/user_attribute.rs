@@ -0,0 +1,82 @@
+use std::{panic, ptr, slice};
+
+use pgp::packet::UserAttribute;
+use pgp::types::Version;
+
+/// An opaque handle to a parsed `UserAttribute` packet.
+///
+/// Must be released with `pgp_user_attribute_free`.
+pub struct PgpUserAttribute(pub(crate) UserAttribute);
+
+/// C-mapped tag for a `UserAttribute`'s variant.
+///
+/// cbindgen turns this into a type-safe `enum class` in the generated C++
+/// header, rather than C++ callers having to compare `UserAttribute::to_u8()`
+/// against raw `uint8_t` constants.
+#[repr(u8)]
+pub enum PgpUserAttributeTag {
+    Image = 1,
+    Unknown = 0,
+}
+
+/// Returns the tag of the `UserAttribute` behind `handle`.
+///
+/// # Safety
+/// `handle` must be a live pointer returned by `pgp_user_attribute_parse`.
+#[no_mangle]
+pub unsafe extern "C" fn pgp_user_attribute_tag(
+    handle: *const PgpUserAttribute,
+) -> PgpUserAttributeTag {
+    match (*handle).0 {
+        UserAttribute::Image { .. } => PgpUserAttributeTag::Image,
+        UserAttribute::Unknown { .. } => PgpUserAttributeTag::Unknown,
+    }
+}
+
+/// Parses a `UserAttribute` packet from the `len` bytes at `ptr`.
+///
+/// Returns `NULL` if the input could not be parsed, including when parsing
+/// panics (e.g. on malformed, attacker-controlled bytes) — a panic must
+/// never unwind across this `extern "C"` boundary.
+///
+/// # Safety
+/// `ptr` must point to at least `len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn pgp_user_attribute_parse(
+    version: u8,
+    ptr: *const u8,
+    len: usize,
+) -> *mut PgpUserAttribute {
+    let result = panic::catch_unwind(|| {
+        let packet_version = if version == 0 {
+            Version::Old
+        } else {
+            Version::New
+        };
+
+        let input = slice::from_raw_parts(ptr, len);
+        UserAttribute::from_slice(packet_version, input)
+    });
+
+    match result {
+        Ok(Ok(attr)) => Box::into_raw(Box::new(PgpUserAttribute(attr))),
+        Ok(Err(_)) | Err(_) => ptr::null_mut(),
+    }
+}
+
+// `pgp_user_attribute_serialize` is generated by `build.rs` under the
+// `ffi` feature (see `SHIM_TYPES` there), rather than hand-written here,
+// since every `Serialize` implementor needs the same boilerplate.
+
+/// Releases a `PgpUserAttribute` handle.
+///
+/// # Safety
+/// `handle` must be a live pointer returned by `pgp_user_attribute_parse`
+/// and must not be freed more than once.
+#[no_mangle]
+pub unsafe extern "C" fn pgp_user_attribute_free(handle: *mut PgpUserAttribute) {
+    if handle.is_null() {
+        return;
+    }
+    drop(Box::from_raw(handle));
+}
@@ -8,13 +8,100 @@ use ser::Serialize;
 use types::Version;
 use util::{packet_length, write_packet_len};
 
+/// The only header version defined by RFC 4880, so this is the only one we
+/// accept when parsing.
+const IMAGE_HEADER_VERSION: u8 = 1;
+
+/// 2 bytes header length + 1 byte version + 1 byte encoding + 12 reserved bytes.
+const IMAGE_HEADER_LEN: usize = 16;
+
+const IMAGE_HEADER_RESERVED_LEN: usize = 12;
+
+/// The encoding of the image carried by an `Image` user attribute subpacket.
+/// https://tools.ietf.org/html/rfc4880.html#section-5.12.1
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ImageEncoding {
+    Jpeg,
+    Unknown(u8),
+}
+
+impl ImageEncoding {
+    fn from_u8(v: u8) -> Self {
+        match v {
+            1 => ImageEncoding::Jpeg,
+            _ => ImageEncoding::Unknown(v),
+        }
+    }
+
+    fn to_u8(&self) -> u8 {
+        match self {
+            ImageEncoding::Jpeg => 1,
+            ImageEncoding::Unknown(v) => *v,
+        }
+    }
+
+    /// The MIME type of the image data, if known.
+    pub fn mime_type(&self) -> Option<&'static str> {
+        match self {
+            ImageEncoding::Jpeg => Some("image/jpeg"),
+            ImageEncoding::Unknown(_) => None,
+        }
+    }
+
+    /// The conventional file extension for the image data, if known.
+    pub fn file_extension(&self) -> Option<&'static str> {
+        match self {
+            ImageEncoding::Jpeg => Some("jpg"),
+            ImageEncoding::Unknown(_) => None,
+        }
+    }
+}
+
+/// The parsed header of an `Image` user attribute subpacket.
+/// https://tools.ietf.org/html/rfc4880.html#section-5.12.1
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ImageHeader {
+    // `header_len` and `version` are kept around only so
+    // `UserAttribute::from_slice` can reject an unsupported header length or
+    // version with a message naming the offending value; both are always
+    // their canonical values (`IMAGE_HEADER_LEN`, `IMAGE_HEADER_VERSION`) on
+    // a value returned from `from_slice`.
+    header_len: u16,
+    version: u8,
+    encoding: ImageEncoding,
+}
+
+impl ImageHeader {
+    pub fn new(encoding: ImageEncoding) -> Self {
+        ImageHeader {
+            header_len: IMAGE_HEADER_LEN as u16,
+            version: IMAGE_HEADER_VERSION,
+            encoding,
+        }
+    }
+
+    pub fn encoding(&self) -> ImageEncoding {
+        self.encoding
+    }
+
+    /// The MIME type of the image data, if known.
+    pub fn mime_type(&self) -> Option<&'static str> {
+        self.encoding.mime_type()
+    }
+
+    /// The conventional file extension for the image data, if known.
+    pub fn file_extension(&self) -> Option<&'static str> {
+        self.encoding.file_extension()
+    }
+}
+
 /// User Attribute Packet
 /// https://tools.ietf.org/html/rfc4880.html#section-5.12
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum UserAttribute {
     Image {
         packet_version: Version,
-        header: Vec<u8>,
+        header: ImageHeader,
         data: Vec<u8>,
     },
     Unknown {
@@ -29,6 +116,29 @@ impl UserAttribute {
     pub fn from_slice(packet_version: Version, input: &[u8]) -> Result<Self> {
         let (_, pk) = parse(input, packet_version)?;
 
+        if let UserAttribute::Image { ref header, .. } = pk {
+            // Checked here, rather than while parsing, so both failures name
+            // the offending value instead of surfacing as a generic nom
+            // parse error. A non-canonical length is rejected outright
+            // rather than preserved, since accepting it would otherwise
+            // either silently drop trailing bytes on serialize (length >
+            // `IMAGE_HEADER_LEN`) or fail to round-trip at all (length <
+            // `IMAGE_HEADER_LEN`, too short for `Serialize` to reconstruct).
+            if header.header_len != IMAGE_HEADER_LEN as u16 {
+                bail!(
+                    "unsupported image user attribute header length: {} (expected {})",
+                    header.header_len,
+                    IMAGE_HEADER_LEN
+                );
+            }
+            if header.version != IMAGE_HEADER_VERSION {
+                bail!(
+                    "unsupported image user attribute header version: {}",
+                    header.version
+                );
+            }
+        }
+
         Ok(pk)
     }
 
@@ -50,7 +160,7 @@ impl UserAttribute {
         match self {
             UserAttribute::Image { ref data, .. } => {
                 // typ + image header + data length
-                1 + 16 + data.len()
+                1 + IMAGE_HEADER_LEN + data.len()
             }
             UserAttribute::Unknown { ref data, .. } => {
                 // typ + data length
@@ -58,6 +168,24 @@ impl UserAttribute {
             }
         }
     }
+
+    /// The MIME type of the embedded image, if this is an `Image` attribute
+    /// with a known encoding.
+    pub fn mime_type(&self) -> Option<&'static str> {
+        match self {
+            UserAttribute::Image { ref header, .. } => header.mime_type(),
+            UserAttribute::Unknown { .. } => None,
+        }
+    }
+
+    /// The conventional file extension of the embedded image, if this is an
+    /// `Image` attribute with a known encoding.
+    pub fn file_extension(&self) -> Option<&'static str> {
+        match self {
+            UserAttribute::Image { ref header, .. } => header.file_extension(),
+            UserAttribute::Unknown { .. } => None,
+        }
+    }
 }
 
 impl fmt::Display for UserAttribute {
@@ -75,14 +203,25 @@ impl fmt::Display for UserAttribute {
 
 #[rustfmt::skip]
 named_args!(image(packet_version: Version) <UserAttribute>, do_parse!(
-    // little endian, for historical reasons..
-       header_len: le_u16
-    >>     header: take!(header_len - 2)
+    // little endian, for historical reasons.. guard against the `- 2` below
+    // underflowing on a truncated header instead of panicking.
+       header_len: verify!(le_u16, |len| len >= 2)
+    // Captured whole, however long it actually is, rather than parsed field
+    // by field: a `header_len` other than `IMAGE_HEADER_LEN` is rejected by
+    // `UserAttribute::from_slice` afterwards, so slicing out a fixed-size
+    // version/encoding/reserved structure here would either panic on a too
+    // short body or silently drop trailing bytes on a too long one before
+    // that check ever runs.
+    >>       body: take!(header_len - 2)
     // the actual image is the rest
-    >>         img: rest
+    >>        img: rest
     >> (UserAttribute::Image {
         packet_version,
-        header: header.to_vec(),
+        header: ImageHeader {
+            header_len,
+            version: body.get(0).cloned().unwrap_or_default(),
+            encoding: ImageEncoding::from_u8(body.get(1).cloned().unwrap_or_default()),
+        },
         data: img.to_vec()
     })
 ));
@@ -116,8 +255,9 @@ impl Serialize for UserAttribute {
             } => {
                 // typ: image
                 writer.write_all(&[0x01])?;
-                writer.write_u16::<LittleEndian>((header.len() + 2) as u16)?;
-                writer.write_all(header)?;
+                writer.write_u16::<LittleEndian>(IMAGE_HEADER_LEN as u16)?;
+                writer.write_all(&[IMAGE_HEADER_VERSION, header.encoding().to_u8()])?;
+                writer.write_all(&[0u8; IMAGE_HEADER_RESERVED_LEN])?;
 
                 // actual data
                 writer.write_all(data)?;
@@ -130,3 +270,42 @@ impl Serialize for UserAttribute {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Builds the bytes of an `Image` user attribute packet with the given
+    // header version, encoding and image data.
+    fn image_packet(version: u8, encoding: u8, data: &[u8]) -> Vec<u8> {
+        let mut packet = Vec::new();
+        packet.push((1 + IMAGE_HEADER_LEN + data.len()) as u8);
+        packet.push(0x01); // typ: image
+        packet.write_u16::<LittleEndian>(IMAGE_HEADER_LEN as u16).unwrap();
+        packet.push(version);
+        packet.push(encoding);
+        packet.extend_from_slice(&[0u8; IMAGE_HEADER_RESERVED_LEN]);
+        packet.extend_from_slice(data);
+        packet
+    }
+
+    #[test]
+    fn image_attribute_round_trips() {
+        let input = image_packet(IMAGE_HEADER_VERSION, 1, &[0xff, 0xd8, 0xff, 0xe0]);
+
+        let attr = UserAttribute::from_slice(Version::New, &input).unwrap();
+        assert_eq!(attr.mime_type(), Some("image/jpeg"));
+
+        let mut output = Vec::new();
+        attr.to_writer(&mut output).unwrap();
+        assert_eq!(output, input);
+    }
+
+    #[test]
+    fn image_attribute_rejects_bad_header_version() {
+        let input = image_packet(2, 1, &[0xff, 0xd8]);
+
+        let err = UserAttribute::from_slice(Version::New, &input).unwrap_err();
+        assert!(format!("{}", err).contains("version"));
+    }
+}